@@ -1,10 +1,25 @@
 extern crate gcc;
 
+use std::env;
+
 fn main() {
     gcc::Config::new()
         .cpp(true)
         .file("src/exceptions-wrapper.cpp")
         .flag("-std=c++11")
         .compile("libcpp_exceptions_wrapper.a");
+
+    // The Objective-C bridge (src/objc.rs) is Apple-only, so only build and
+    // link its native half when targeting Apple platforms.
+    if env::var("CARGO_CFG_TARGET_OS").map(|os| os == "macos" || os == "ios").unwrap_or(false) {
+        gcc::Config::new()
+            .cpp(true)
+            .file("src/objc-exceptions-wrapper.mm")
+            .flag("-std=c++11")
+            .flag("-fobjc-arc")
+            .compile("libobjc_exceptions_wrapper.a");
+
+        println!("cargo:rustc-link-lib=framework=Foundation");
+    }
 }
 
@@ -1,14 +1,49 @@
 extern crate libc;
 
 use std::mem;
+use std::ptr;
 use std::any::Any;
+use std::error::Error;
 use std::ffi::CStr;
 
+/// The Objective-C `@try`/`@throw` bridge, for Rust<->ObjC FFI on Apple
+/// platforms. Not compiled or linked on other targets (see build.rs).
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+pub mod objc;
+
 pub trait Exception : Any {
     fn what(&self) -> &str;
 
+    /// The dynamic type identity of this exception, if known.
+    fn type_name(&self) -> Option<&str> {
+        Some(::std::any::type_name::<Self>())
+    }
+
     #[doc(hidden)]
     fn cpp_exception(&self) -> *mut libc::c_void { std::ptr::null_mut() }
+
+    #[doc(hidden)]
+    fn objc_exception(&self) -> *mut libc::c_void { std::ptr::null_mut() }
+
+    /// The immediate cause of this exception, if it wraps one (mirrors
+    /// `std::error::Error::source`).
+    fn source(&self) -> Option<&(Error + 'static)> { None }
+
+    /// Walk the causal chain behind this exception (see `source`),
+    /// innermost-last.
+    fn source_chain(&self) -> SourceChain {
+        SourceChain { current: self.source() }
+    }
+
+    // A default body here would be checked generically (no implicit
+    // `Self: Sized`), so `self`/`Box<Self>` can't coerce to `&Any`/`Box<Any>`
+    // without breaking dispatch through `&Exception`/`Box<Exception>`. Every
+    // impl provides the same one-line body.
+    #[doc(hidden)]
+    fn as_any(&self) -> &Any;
+
+    #[doc(hidden)]
+    fn as_any_box(self: Box<Self>) -> Box<Any>;
 }
 
 pub trait Rethrow {
@@ -36,6 +71,10 @@ extern {
     fn cpp_rethrow(exception: *mut libc::c_void) -> !;
     fn cpp_exception_what(exception: *mut libc::c_void) -> *const libc::c_char;
     fn cpp_exception_destroy(exception: *mut libc::c_void);
+
+    // Returns the demangled `typeid(e).name()` of a caught C++ exception, or
+    // null if `e` is not derived from `std::exception`.
+    fn cpp_exception_type(exception: *mut libc::c_void) -> *const libc::c_char;
 }
 
 struct NativeCppExceptionWrapper {
@@ -58,9 +97,23 @@ impl Exception for NativeCppExceptionWrapper {
         }
     }
 
+    fn type_name(&self) -> Option<&str> {
+        unsafe {
+            let c_str = cpp_exception_type(self.exception);
+            if c_str.is_null() {
+                None
+            } else {
+                CStr::from_ptr(c_str).to_str().ok()
+            }
+        }
+    }
+
     fn cpp_exception(&self) -> *mut libc::c_void {
         self.exception
     }
+
+    fn as_any(&self) -> &Any { self }
+    fn as_any_box(self: Box<Self>) -> Box<Any> { self }
 }
 struct ThrowState<T, F: FnOnce() -> T> {
     try_block: Option<F>,
@@ -77,7 +130,162 @@ extern fn try_internal<T, F: FnOnce() -> T>(state: *mut ThrowState<T, F>) {
     borrowed_state.returned_value = Some(value);
 }
 
-pub fn try<T, F: FnOnce() -> T>(func: F) -> Result<T, Box<Exception>> {
+/// Inline storage for the small-object optimization below: two machine
+/// words, the same size as a whole `FakeTraitObject`, and word-aligned.
+/// It holds any value that `fits_inline` accepts.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct InlineBuffer([usize; 2]);
+
+fn fits_inline<T>() -> bool {
+    mem::size_of::<T>() <= mem::size_of::<InlineBuffer>() &&
+    mem::align_of::<T>() <= mem::align_of::<InlineBuffer>()
+}
+
+thread_local! {
+    // Relay storage for a thrown exception's bytes while they cross the C++
+    // unwind, which only carries a `FakeTraitObject`'s two words by value.
+    // `throw_inline`/`throw_caught_exception` write into this slot and `try`
+    // copies out of it (see `CaughtException`) before it can be reused by a
+    // subsequent throw on this thread.
+    static INLINE_SLOT: ::std::cell::UnsafeCell<InlineBuffer> =
+        ::std::cell::UnsafeCell::new(InlineBuffer([0; 2]));
+
+    // Whether `INLINE_SLOT` currently holds live, not-yet-retrieved bytes.
+    // A Drop running during this exception's own unwind can legally throw
+    // again (e.g. to report a cleanup failure); if that nested exception
+    // also fits inline, it would silently clobber the outer one's bytes in
+    // the same slot before the outer `try` ever reads them. Guard against
+    // that instead of letting it corrupt memory.
+    static INLINE_SLOT_OWNED: ::std::cell::Cell<bool> = ::std::cell::Cell::new(false);
+}
+
+fn claim_inline_slot() {
+    let already_owned = INLINE_SLOT_OWNED.with(|owned| owned.replace(true));
+    assert!(!already_owned,
+            "nested throw of a small exception while the inline relay slot is \
+             still holding an unconsumed one on this thread (likely a Drop \
+             that threw during another exception's unwind)");
+}
+
+// The vtable pointer half of a `FakeTraitObject` is always aligned, so we
+// steal its low bit to mark "this FakeTraitObject's data pointer points into
+// the inline relay slot, not a heap allocation".
+fn tag_inline(vtable: *mut libc::c_void) -> *mut libc::c_void {
+    ((vtable as usize) | 1) as *mut libc::c_void
+}
+
+fn untag_vtable(tagged: *mut libc::c_void) -> (*mut libc::c_void, bool) {
+    let addr = tagged as usize;
+    ((addr & !1usize) as *mut libc::c_void, addr & 1 != 0)
+}
+
+fn throw_inline<T: Exception>(exception: T) -> ! {
+    debug_assert!(fits_inline::<T>());
+    let vtable = {
+        let trait_ref: &Exception = &exception;
+        let fat: FakeTraitObject = unsafe { mem::transmute(trait_ref) };
+        fat.p1
+    };
+    claim_inline_slot();
+    let data_ptr = INLINE_SLOT.with(|cell| cell.get()) as *mut u8;
+    unsafe {
+        ptr::write(data_ptr as *mut T, exception);
+        cpp_throw_rust(FakeTraitObject { p0: data_ptr as *mut libc::c_void, p1: tag_inline(vtable) })
+    }
+}
+
+/// A caught exception: either a C++/Objective-C/Rust exception recovered by
+/// `try`, or one rethrown from such a value.
+///
+/// Behaves like a `Box<Exception>` (it derefs to `Exception` and implements
+/// `Rethrow`), but avoids a heap allocation for small Rust exceptions by
+/// storing them inline instead of boxing them.
+pub struct CaughtException(CaughtExceptionRepr);
+
+enum CaughtExceptionRepr {
+    Inline { buffer: InlineBuffer, vtable: *mut libc::c_void },
+    Boxed(Box<Exception>),
+}
+
+impl CaughtException {
+    fn fat_pointer(&self) -> FakeTraitObject {
+        match self.0 {
+            CaughtExceptionRepr::Inline { ref buffer, vtable } => {
+                FakeTraitObject { p0: buffer as *const InlineBuffer as *mut libc::c_void, p1: vtable }
+            }
+            CaughtExceptionRepr::Boxed(ref boxed) => {
+                let trait_ref: &Exception = &**boxed;
+                unsafe { mem::transmute(trait_ref) }
+            }
+        }
+    }
+
+    /// Attempt to recover the original concrete exception type after a
+    /// `try(...)` call returned `Err`.
+    ///
+    /// On a type mismatch, the original `CaughtException` is returned
+    /// unchanged so it can still be inspected or rethrown.
+    pub fn downcast<T: Exception>(self) -> Result<Box<T>, CaughtException> {
+        if self.downcast_ref::<T>().is_none() {
+            return Err(self);
+        }
+        let mut this = mem::ManuallyDrop::new(self);
+        match this.0 {
+            CaughtExceptionRepr::Boxed(ref mut boxed) => {
+                let boxed = unsafe { ptr::read(boxed) };
+                Ok(boxed.as_any_box().downcast::<T>().unwrap())
+            }
+            CaughtExceptionRepr::Inline { ref buffer, .. } => {
+                let value: T = unsafe { ptr::read(buffer as *const InlineBuffer as *const T) };
+                Ok(Box::new(value))
+            }
+        }
+    }
+
+    /// Like `downcast`, but borrows instead of consuming the exception.
+    pub fn downcast_ref<T: Exception>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    /// Dispatch to `on_match` if the dynamic type of this exception is `T`,
+    /// otherwise fall through to `fallback` with the exception unchanged.
+    ///
+    /// The single-clause primitive behind the `handle!` macro.
+    pub fn handle<T, R, F, G>(self, on_match: F, fallback: G) -> R
+        where T: Exception,
+              F: FnOnce(T) -> R,
+              G: FnOnce(CaughtException) -> R
+    {
+        match self.downcast::<T>() {
+            Ok(concrete) => on_match(*concrete),
+            Err(original) => fallback(original)
+        }
+    }
+}
+
+impl ::std::ops::Deref for CaughtException {
+    type Target = Exception;
+
+    fn deref(&self) -> &Exception {
+        unsafe { mem::transmute(self.fat_pointer()) }
+    }
+}
+
+impl Drop for CaughtException {
+    fn drop(&mut self) {
+        if let CaughtExceptionRepr::Inline { .. } = &self.0 {
+            let fat = self.fat_pointer();
+            unsafe {
+                let raw: *mut Exception = mem::transmute(fat);
+                ptr::drop_in_place(raw);
+            }
+        }
+        // The `Boxed` variant drops (and deallocates) normally.
+    }
+}
+
+pub fn try<T, F: FnOnce() -> T>(func: F) -> Result<T, CaughtException> {
     let mut state = ThrowState {
         try_block: Some(func),
         returned_value: None
@@ -93,18 +301,37 @@ pub fn try<T, F: FnOnce() -> T>(func: F) -> Result<T, Box<Exception>> {
 
     state.returned_value.ok_or_else(|| {
         if caught_rust {
-            unsafe {
-                let ex: *mut Exception = mem::transmute(exception);
-                Box::<Exception>::from_raw(ex)
+            let (vtable, inline) = untag_vtable(exception.p1);
+            if inline {
+                let buffer: InlineBuffer = unsafe { *(exception.p0 as *const InlineBuffer) };
+                INLINE_SLOT_OWNED.with(|owned| owned.set(false));
+                CaughtException(CaughtExceptionRepr::Inline { buffer: buffer, vtable: vtable })
+            } else {
+                unsafe {
+                    let ex: *mut Exception = mem::transmute(FakeTraitObject { p0: exception.p0, p1: vtable });
+                    CaughtException(CaughtExceptionRepr::Boxed(Box::<Exception>::from_raw(ex)))
+                }
             }
         } else {
             let ex = NativeCppExceptionWrapper { exception: exception.p0 };
-            let bex: Box<Exception> = Box::new(ex);
-            bex
+            CaughtException(CaughtExceptionRepr::Boxed(Box::new(ex)))
         }
     })
 }
 
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn rethrow_objc_exception(exception: *mut libc::c_void) -> ! {
+    objc::rethrow_native(exception)
+}
+
+// `objc_exception()` only ever returns non-null from `NativeObjcExceptionWrapper`,
+// which doesn't exist off Apple (see `pub mod objc` above), so this is dead code
+// there; it just needs to satisfy the type-checker without linking `objc`.
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn rethrow_objc_exception(_exception: *mut libc::c_void) -> ! {
+    unreachable!("objc exceptions don't exist off Apple platforms")
+}
+
 fn throw_boxed_exception(boxed: Box<Exception>) -> ! {
     let cpp_ex = boxed.cpp_exception();
     if !cpp_ex.is_null() {
@@ -112,15 +339,54 @@ fn throw_boxed_exception(boxed: Box<Exception>) -> ! {
         // once. Rethrow it instead.
         unsafe { cpp_rethrow(cpp_ex) }
     }
-    else {
-        let ex: FakeTraitObject = unsafe { mem::transmute(Box::into_raw(boxed)) };
-        unsafe { cpp_throw_rust(ex) }
+
+    let objc_ex = boxed.objc_exception();
+    if !objc_ex.is_null() {
+        // Likewise for an Objective-C exception we have already caught once.
+        rethrow_objc_exception(objc_ex)
+    }
+
+    let ex: FakeTraitObject = unsafe { mem::transmute(Box::into_raw(boxed)) };
+    unsafe { cpp_throw_rust(ex) }
+}
+
+fn throw_caught_exception(exception: CaughtException) -> ! {
+    let mut exception = mem::ManuallyDrop::new(exception);
+    match exception.0 {
+        CaughtExceptionRepr::Boxed(ref mut boxed) => {
+            let boxed = unsafe { ptr::read(boxed) };
+            throw_boxed_exception(boxed)
+        }
+        CaughtExceptionRepr::Inline { ref buffer, vtable } => {
+            claim_inline_slot();
+            let data_ptr = INLINE_SLOT.with(|cell| cell.get()) as *mut u8;
+            unsafe {
+                ptr::copy_nonoverlapping(buffer as *const InlineBuffer as *const u8,
+                                         data_ptr,
+                                         mem::size_of::<InlineBuffer>());
+                cpp_throw_rust(FakeTraitObject { p0: data_ptr as *mut libc::c_void, p1: tag_inline(vtable) })
+            }
+        }
     }
 }
 
 pub fn throw<T: Exception>(exception: T) -> ! {
-    let boxed: Box<Exception> = Box::new(exception);
-    throw_boxed_exception(boxed)
+    if fits_inline::<T>() {
+        // Already-caught native exceptions are small enough to fit inline
+        // too, but they must go back out through their own runtime's
+        // rethrow, not get relayed as if they were a fresh Rust exception.
+        let cpp_ex = exception.cpp_exception();
+        if !cpp_ex.is_null() {
+            unsafe { cpp_rethrow(cpp_ex) }
+        }
+        let objc_ex = exception.objc_exception();
+        if !objc_ex.is_null() {
+            rethrow_objc_exception(objc_ex)
+        }
+        throw_inline(exception)
+    } else {
+        throw_boxed_exception(Box::new(exception))
+    }
 }
 
 impl Rethrow for Box<Exception> {
@@ -129,6 +395,12 @@ impl Rethrow for Box<Exception> {
     }
 }
 
+impl Rethrow for CaughtException {
+    fn rethrow(self) -> ! {
+        throw_caught_exception(self)
+    }
+}
+
 impl<T> Rethrow for T where T: Exception {
     fn rethrow(self) -> ! {
         throw(self)
@@ -144,6 +416,146 @@ impl<T> UnwrapOrRethrow<T> for Result<T, Box<Exception>> {
     }
 }
 
+impl<T> UnwrapOrRethrow<T> for Result<T, CaughtException> {
+    fn unwrap_or_rethrow(self) -> T {
+        match self {
+            Ok(x) => x,
+            Err(ex) => ex.rethrow()
+        }
+    }
+}
+
+/// Wraps any `std::error::Error` as an `Exception`.
+///
+/// `what()` is the error's `Display` text, captured at construction time
+/// since `Exception::what` must return a borrowed `&str`.
+pub struct ErrorException<E> {
+    error: E,
+    message: String,
+}
+
+impl<E: Error> ErrorException<E> {
+    pub fn new(error: E) -> Self {
+        let message = error.to_string();
+        ErrorException { error: error, message: message }
+    }
+
+    /// The original error that was wrapped.
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+}
+
+impl<E: Error + Send + 'static> Exception for ErrorException<E> {
+    fn what(&self) -> &str {
+        &self.message
+    }
+
+    fn source(&self) -> Option<&(Error + 'static)> {
+        self.error.source()
+    }
+
+    fn as_any(&self) -> &Any { self }
+    fn as_any_box(self: Box<Self>) -> Box<Any> { self }
+}
+
+/// An iterator over an exception's `source()` chain, as returned by
+/// `Exception::source_chain`.
+pub struct SourceChain<'a> {
+    current: Option<&'a (Error + 'static)>,
+}
+
+impl<'a> Iterator for SourceChain<'a> {
+    type Item = &'a (Error + 'static);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.take();
+        self.current = current.and_then(|e| e.source());
+        current
+    }
+}
+
+/// Throw any `std::error::Error`, wrapping it in an `ErrorException`.
+pub fn throw_error<E: Error + Send + 'static>(error: E) -> ! {
+    throw(ErrorException::new(error))
+}
+
+impl Exception {
+    /// Recover the concrete exception type, or `Err(self)` on a type mismatch.
+    pub fn downcast<T: Exception>(self: Box<Self>) -> Result<Box<T>, Box<Exception>> {
+        if self.as_any().is::<T>() {
+            Ok(self.as_any_box().downcast::<T>().unwrap())
+        } else {
+            Err(self)
+        }
+    }
+
+    /// Like `downcast`, but borrows instead of consuming the exception.
+    pub fn downcast_ref<T: Exception>(&self) -> Option<&T> {
+        self.as_any().downcast_ref::<T>()
+    }
+
+    /// Dispatch to `on_match` if the dynamic type of this exception is `T`,
+    /// otherwise fall through to `fallback` with the exception unchanged.
+    ///
+    /// This is the single-clause primitive behind the `handle!` macro, which
+    /// chains calls to this method to emulate a cascade of C++ `catch`
+    /// clauses over a `Box<Exception>`.
+    pub fn handle<T, R, F, G>(self: Box<Self>, on_match: F, fallback: G) -> R
+        where T: Exception,
+              F: FnOnce(T) -> R,
+              G: FnOnce(Box<Exception>) -> R
+    {
+        match self.downcast::<T>() {
+            Ok(concrete) => on_match(*concrete),
+            Err(original) => fallback(original)
+        }
+    }
+}
+
+/// Like `CaughtException::handle`, but operating on the `Err` side of a
+/// `Result`, passing `Ok` values straight through.
+pub fn handle<T, R, F, G>(result: Result<R, CaughtException>, on_match: F, fallback: G) -> R
+    where T: Exception,
+          F: FnOnce(T) -> R,
+          G: FnOnce(CaughtException) -> R
+{
+    match result {
+        Ok(value) => value,
+        Err(ex) => ex.handle(on_match, fallback)
+    }
+}
+
+/// Cascade a caught exception through a list of typed handlers, in order,
+/// like a chain of C++ `catch` clauses.
+///
+/// ```ignore
+/// handle!(ex,
+///     |e: SomeType| { ... },
+///     |e: OtherType| { ... },
+///     |e| { /* catch-all */ });
+/// ```
+///
+/// Each typed arm runs the first time the dynamic type of `ex` matches; the
+/// final, untyped arm is the catch-all and always receives the original
+/// exception if nothing else matched. Native C++ exceptions
+/// (`NativeCppExceptionWrapper`) never match a user Rust type and always
+/// fall through to the catch-all unless a dedicated matcher for that
+/// wrapper type is provided. Works on anything with an inherent `handle`
+/// method of this shape, i.e. both `CaughtException` and `Box<Exception>`.
+#[macro_export]
+macro_rules! handle {
+    ($ex:expr, |$pat:pat| $body:expr) => {
+        (move |$pat| $body)($ex)
+    };
+    ($ex:expr, |$pat:pat| $body:expr,) => {
+        (move |$pat| $body)($ex)
+    };
+    ($ex:expr, |$pat:ident : $ty:ty| $body:expr, $($rest:tt)+) => {
+        $ex.handle(move |$pat: $ty| $body, move |__handle_ex| handle!(__handle_ex, $($rest)+))
+    };
+}
+
 #[cfg(test)]
 mod test {
     use std::borrow::Borrow;
@@ -165,6 +577,9 @@ mod test {
         fn what(&self) -> &str {
             self.message.as_ref()
         }
+
+        fn as_any(&self) -> &Any { self }
+        fn as_any_box(self: Box<Self>) -> Box<Any> { self }
     }
 
     struct Droppable<'a> {
@@ -254,5 +669,204 @@ mod test {
         assert_eq!(r2.unwrap_err().what(), "Rust Exception");
     }
 
+    struct SmallDroppableException {
+        value: u32,
+        dropped: *mut bool,
+    }
+
+    impl Exception for SmallDroppableException {
+        fn what(&self) -> &str { "small droppable" }
+
+        fn as_any(&self) -> &Any { self }
+        fn as_any_box(self: Box<Self>) -> Box<Any> { self }
+    }
+
+    impl Drop for SmallDroppableException {
+        fn drop(&mut self) {
+            unsafe { *self.dropped = true; }
+        }
+    }
+
+    #[test]
+    fn test_small_exception_uses_inline_storage_and_drops_once() {
+        assert!(mem::size_of::<SmallDroppableException>() <= mem::size_of::<[usize; 2]>());
+        let mut dropped = false;
+        {
+            let result = try(|| {
+                throw(SmallDroppableException { value: 7, dropped: &mut dropped as *mut bool });
+            });
+            let ex = result.unwrap_err();
+            assert_eq!(ex.downcast_ref::<SmallDroppableException>().unwrap().value, 7);
+            assert!(!dropped);
+        }
+        assert!(dropped);
+    }
+
+    #[test]
+    fn test_rethrow_small_exception() {
+        let mut dropped = false;
+        let r2 = try(|| {
+            let r1 = try(|| {
+                throw(SmallDroppableException { value: 9, dropped: &mut dropped as *mut bool });
+            });
+            assert!(r1.is_err());
+            r1.unwrap_err().rethrow();
+        });
+        assert!(r2.is_err());
+        let ex = r2.unwrap_err();
+        assert_eq!(ex.downcast_ref::<SmallDroppableException>().unwrap().value, 9);
+    }
+
+    struct OtherException;
+
+    impl Exception for OtherException {
+        fn what(&self) -> &str { "other" }
+
+        fn as_any(&self) -> &Any { self }
+        fn as_any_box(self: Box<Self>) -> Box<Any> { self }
+    }
+
+    #[derive(Debug)]
+    struct InnerError;
+
+    impl std::fmt::Display for InnerError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "inner error")
+        }
+    }
+
+    impl std::error::Error for InnerError {}
+
+    #[derive(Debug)]
+    struct OuterError;
+
+    impl std::fmt::Display for OuterError {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "outer error")
+        }
+    }
+
+    impl std::error::Error for OuterError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&InnerError)
+        }
+    }
+
+    #[test]
+    fn test_type_name_identifies_rust_exception() {
+        let result = try(|| {
+            throw(TestException{message: "Hello, World!".into()});
+        });
+        let ex = result.unwrap_err();
+        assert!(ex.type_name().unwrap().ends_with("TestException"));
+    }
+
+    #[test]
+    fn test_type_name_identifies_cpp_exception() {
+        let result = try(|| {
+            unsafe {
+                let message = std::ffi::CString::new("Hello from C++!").unwrap();
+                let msg_cstr: &CStr = message.borrow();
+                cpp_throw_test_exception(msg_cstr.as_ptr());
+            }
+        });
+        let ex = result.unwrap_err();
+        assert!(ex.type_name().is_some());
+    }
+
+    #[test]
+    fn test_throw_error_bridges_std_error() {
+        let result = try(|| {
+            throw_error(OuterError);
+        });
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().what(), "outer error");
+    }
+
+    #[test]
+    fn test_error_exception_source_chain() {
+        let exception = ErrorException::new(OuterError);
+        let chain: Vec<String> = exception.source_chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain, vec!["inner error".to_string()]);
+    }
+
+    #[test]
+    fn test_source_chain_survives_throw_and_catch_round_trip() {
+        let result = try(|| {
+            throw_error(OuterError);
+        });
+        let ex = result.unwrap_err();
+        let chain: Vec<String> = ex.source_chain().map(|e| e.to_string()).collect();
+        assert_eq!(chain, vec!["inner error".to_string()]);
+    }
+
+    #[test]
+    fn test_downcast_ref_recovers_concrete_type() {
+        let result = try(|| {
+            throw(TestException{message: "Rust Exception".into()});
+        });
+        let ex = result.unwrap_err();
+        let concrete = ex.downcast_ref::<TestException>().expect("should downcast");
+        assert_eq!(concrete.message, "Rust Exception");
+    }
+
+    #[test]
+    fn test_downcast_mismatch_returns_original() {
+        let result = try(|| {
+            throw(TestException{message: "Rust Exception".into()});
+        });
+        let ex = result.unwrap_err();
+        let ex = ex.downcast::<OtherException>().err().expect("should not downcast");
+        assert_eq!(ex.what(), "Rust Exception");
+    }
+
+    #[test]
+    fn test_handle_dispatches_to_matching_type() {
+        let result = try(|| {
+            throw(TestException{message: "Rust Exception".into()});
+        });
+        let ex = result.unwrap_err();
+        let handled = handle!(ex,
+            |_e: OtherException| "other".len(),
+            |e: TestException| e.message.len(),
+            |_e| 0);
+        assert_eq!(handled, "Rust Exception".len());
+    }
+
+    #[test]
+    fn test_handle_falls_through_to_catch_all() {
+        let result = try(|| {
+            throw(OtherException);
+        });
+        let ex = result.unwrap_err();
+        let handled = handle!(ex,
+            |e: TestException| e.what().len(),
+            |_e| 42);
+        assert_eq!(handled, 42);
+    }
+
+    struct TinyException;
+
+    impl Exception for TinyException {
+        fn what(&self) -> &str { "tiny" }
+
+        fn as_any(&self) -> &Any { self }
+        fn as_any_box(self: Box<Self>) -> Box<Any> { self }
+    }
+
+    #[test]
+    fn test_nested_inline_throw_is_rejected_while_slot_is_owned() {
+        // Simulates a Drop that throws another small exception while the
+        // first one is still mid-unwind (see `INLINE_SLOT_OWNED`): the
+        // relay slot must refuse reuse instead of silently corrupting the
+        // exception still in flight.
+        INLINE_SLOT_OWNED.with(|owned| owned.set(true));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            throw_inline(TinyException)
+        }));
+        INLINE_SLOT_OWNED.with(|owned| owned.set(false));
+        assert!(result.is_err());
+    }
+
 }
 
@@ -0,0 +1,118 @@
+//! Objective-C `@try`/`@throw` bridge, mirroring the C++ one in the crate root.
+
+use libc;
+use std::mem;
+use std::any::Any;
+use std::ffi::CStr;
+
+use {CaughtException, CaughtExceptionRepr, Exception};
+
+#[link(name = "objc_exceptions_wrapper")]
+extern {
+    fn objc_try(block: extern fn(*mut libc::c_void),
+                context: *mut libc::c_void,
+                error: *mut *mut libc::c_void) -> libc::c_int;
+    fn objc_throw(exception: *mut libc::c_void) -> !;
+    fn objc_exception_reason(exception: *mut libc::c_void) -> *mut libc::c_char;
+    fn objc_exception_reason_free(reason: *mut libc::c_char);
+    fn objc_exception_class_name(exception: *mut libc::c_void) -> *const libc::c_char;
+    fn objc_exception_release(exception: *mut libc::c_void);
+}
+
+/// A caught Objective-C exception, type-erased as an `id`.
+///
+/// Wraps the object so it can be inspected through the ordinary `Exception`
+/// trait, and rethrown losslessly (via `@throw`) if it propagates back out
+/// through another `try`.
+pub struct NativeObjcExceptionWrapper {
+    exception: *mut libc::c_void,
+    // `-reason`/`-description` return an autoreleased `NSString`, so we copy
+    // it out at catch time rather than recomputing a transient one on every
+    // `what()` call; it would otherwise dangle once the active autorelease
+    // pool drains.
+    reason: *mut libc::c_char,
+}
+
+impl Drop for NativeObjcExceptionWrapper {
+    fn drop(&mut self) {
+        unsafe {
+            objc_exception_reason_free(self.reason);
+            objc_exception_release(self.exception);
+        }
+    }
+}
+
+impl Exception for NativeObjcExceptionWrapper {
+    fn what(&self) -> &str {
+        unsafe { CStr::from_ptr(self.reason).to_str().unwrap() }
+    }
+
+    fn type_name(&self) -> Option<&str> {
+        unsafe {
+            let c_str = objc_exception_class_name(self.exception);
+            if c_str.is_null() {
+                None
+            } else {
+                CStr::from_ptr(c_str).to_str().ok()
+            }
+        }
+    }
+
+    fn objc_exception(&self) -> *mut libc::c_void {
+        self.exception
+    }
+
+    fn as_any(&self) -> &Any { self }
+    fn as_any_box(self: Box<Self>) -> Box<Any> { self }
+}
+
+struct ThrowState<T, F: FnOnce() -> T> {
+    try_block: Option<F>,
+    returned_value: Option<T>
+}
+
+extern fn try_internal<T, F: FnOnce() -> T>(state: *mut ThrowState<T, F>) {
+    let borrowed_state: &mut ThrowState<T, F> = unsafe {
+        mem::transmute(state)
+    };
+    debug_assert!(borrowed_state.returned_value.is_none());
+
+    let value = (borrowed_state.try_block.take().unwrap())();
+    borrowed_state.returned_value = Some(value);
+}
+
+/// The Objective-C counterpart of the crate's top-level `try`: runs `func`
+/// inside an `@try`/`@catch(id)` block, wrapping any caught object in a
+/// `NativeObjcExceptionWrapper`.
+pub fn try<T, F: FnOnce() -> T>(func: F) -> Result<T, CaughtException> {
+    let mut state = ThrowState {
+        try_block: Some(func),
+        returned_value: None
+    };
+    let mut error: *mut libc::c_void = ::std::ptr::null_mut();
+    let caught = unsafe {
+        let callback = try_internal::<T, F>;
+        let borrowed_state = &mut state;
+        objc_try(mem::transmute(callback),
+                 mem::transmute(borrowed_state),
+                 &mut error)
+    };
+
+    state.returned_value.ok_or_else(|| {
+        debug_assert!(caught != 0 && !error.is_null());
+        let reason = unsafe { objc_exception_reason(error) };
+        let ex: Box<Exception> = Box::new(NativeObjcExceptionWrapper { exception: error, reason: reason });
+        CaughtException(CaughtExceptionRepr::Boxed(ex))
+    })
+}
+
+/// Throw an Objective-C exception (an `id`) via `@throw`, unwinding through
+/// any enclosing `try` or `objc::try`.
+pub fn throw(exception: *mut libc::c_void) -> ! {
+    unsafe { objc_throw(exception) }
+}
+
+#[doc(hidden)]
+pub(crate) fn rethrow_native(exception: *mut libc::c_void) -> ! {
+    unsafe { objc_throw(exception) }
+}